@@ -0,0 +1,146 @@
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A calendar date in the proleptic Gregorian calendar, stored as
+/// `YYYY-MM-DD` on the wire. Used where arithmetic over dates (stepping a
+/// recurring schedule forward) is needed, as opposed to the plain `String`
+/// dates elsewhere in the domain that are only ever compared or echoed back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Parses a `"YYYY-MM-DD"` string.
+    pub fn parse(s: &str) -> Result<Date, String> {
+        let mut parts = s.splitn(3, '-');
+        let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format!("{s:?} is not a valid date (expected YYYY-MM-DD)"));
+        };
+        let year: i32 = year.parse().map_err(|_| format!("{s:?} has an invalid year"))?;
+        let month: u32 = month.parse().map_err(|_| format!("{s:?} has an invalid month"))?;
+        let day: u32 = day.parse().map_err(|_| format!("{s:?} has an invalid day"))?;
+        if !(1..=12).contains(&month) {
+            return Err(format!("{s:?} has a month outside 1-12"));
+        }
+        if day < 1 || day > days_in_month(year, month) {
+            return Err(format!("{s:?} has a day outside the valid range for its month"));
+        }
+        Ok(Date { year, month, day })
+    }
+
+    /// Adds (or subtracts, for negative `n`) a number of whole days.
+    pub fn add_days(self, n: i64) -> Date {
+        let (year, month, day) = civil_from_days(days_from_civil(self.year, self.month, self.day) + n);
+        Date { year, month, day }
+    }
+
+    /// Adds a number of months, clamping the day to the last valid day of
+    /// the target month (e.g. Jan 31 + 1 month -> Feb 28).
+    pub fn add_months(self, n: i64) -> Date {
+        let total = self.year as i64 * 12 + (self.month as i64 - 1) + n;
+        let year = total.div_euclid(12) as i32;
+        let month = (total.rem_euclid(12) + 1) as u32;
+        let day = self.day.min(days_in_month(year, month));
+        Date { year, month, day }
+    }
+
+    /// Adds a number of years, clamping Feb 29 to Feb 28 in non-leap years.
+    pub fn add_years(self, n: i64) -> Date {
+        let year = self.year + n as i32;
+        let day = self.day.min(days_in_month(year, self.month));
+        Date { year, month: self.month, day }
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 => if is_leap_year(year) { 29 } else { 28 },
+        _ => unreachable!("month out of range 1-12"),
+    }
+}
+
+// Howard Hinnant's well-known civil-calendar <-> day-count conversion
+// (days since 1970-01-01), reproduced here since the domain has no date
+// arithmetic library dependency.
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year as i64 - 1 } else { year as i64 };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = if month <= 2 { y + 1 } else { y } as i32;
+    (year, month, day)
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Date::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Date;
+
+    #[test]
+    fn parses_and_displays() {
+        let d = Date::parse("2025-06-20").unwrap();
+        assert_eq!(d.to_string(), "2025-06-20");
+    }
+
+    #[test]
+    fn add_days_crosses_month_and_year_boundaries() {
+        assert_eq!(Date::parse("2025-01-31").unwrap().add_days(1), Date::parse("2025-02-01").unwrap());
+        assert_eq!(Date::parse("2025-12-31").unwrap().add_days(1), Date::parse("2026-01-01").unwrap());
+    }
+
+    #[test]
+    fn add_months_clamps_to_shorter_month() {
+        let jan31 = Date::parse("2025-01-31").unwrap();
+        assert_eq!(jan31.add_months(1), Date::parse("2025-02-28").unwrap());
+    }
+
+    #[test]
+    fn add_years_clamps_leap_day() {
+        let leap_day = Date::parse("2024-02-29").unwrap();
+        assert_eq!(leap_day.add_years(1), Date::parse("2025-02-28").unwrap());
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{Date, Milliunits};
+
+/// One row parsed out of a bank statement, ready to become a `Transaction`
+/// once matched against an account.
+pub struct StatementRow {
+    pub date: String,
+    pub payee: String,
+    pub amount: Milliunits,
+}
+
+/// Outcome of `Budget::import_statement`.
+#[derive(Serialize)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+/// Collapses whitespace and case differences so the same payee printed
+/// slightly differently by a bank (extra spaces, inconsistent casing) still
+/// hashes to the same `import_id`.
+fn normalize_payee(payee: &str) -> String {
+    payee.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase()
+}
+
+/// A deterministic id for a statement row on a given account, used to skip
+/// re-importing the same row on a repeat import. Derived from
+/// `(account_id, date, amount, normalized_payee)`; anything hashing to the
+/// same id is considered a duplicate of an existing transaction.
+pub fn import_id(account_id: Uuid, date: &str, amount: Milliunits, payee: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    account_id.hash(&mut hasher);
+    date.hash(&mut hasher);
+    amount.hash(&mut hasher);
+    normalize_payee(payee).hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Parses a CSV bank statement with `date`, `payee`, and `amount` columns
+/// (column order doesn't matter, header names are matched case-insensitively).
+/// Fields may be double-quoted (with `""` as an escaped quote) to contain a
+/// literal comma, as in a payee like `"SMITH, JOHN"`.
+pub fn parse_csv(input: &str) -> Result<Vec<StatementRow>, String> {
+    let mut lines = input.lines().filter(|line| !line.trim().is_empty());
+    let header = lines.next().ok_or("the statement has no header row")?;
+    let columns: Vec<String> = split_csv_line(header).iter().map(|c| c.trim().to_lowercase()).collect();
+    let date_idx = columns.iter().position(|c| c == "date").ok_or("the statement is missing a \"date\" column")?;
+    let payee_idx = columns.iter().position(|c| c == "payee").ok_or("the statement is missing a \"payee\" column")?;
+    let amount_idx = columns
+        .iter()
+        .position(|c| c == "amount")
+        .ok_or("the statement is missing an \"amount\" column")?;
+
+    let mut rows = Vec::new();
+    for line in lines {
+        let fields = split_csv_line(line);
+        if fields.len() != columns.len() {
+            return Err(format!(
+                "{line:?} has {} column(s) but the header has {}",
+                fields.len(),
+                columns.len()
+            ));
+        }
+        rows.push(StatementRow {
+            date: Date::parse(fields[date_idx].trim())?.to_string(),
+            payee: fields[payee_idx].trim().to_string(),
+            amount: Milliunits::from_decimal_str(fields[amount_idx].trim())?,
+        });
+    }
+    Ok(rows)
+}
+
+/// Splits one CSV line on unquoted commas, honoring double-quoted fields
+/// (with `""` as an escaped literal quote) so a quoted field's commas aren't
+/// mistaken for column separators.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else {
+            match c {
+                '"' => in_quotes = true,
+                ',' => fields.push(std::mem::take(&mut field)),
+                _ => field.push(c),
+            }
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Parses the `<STMTTRN>` blocks out of an OFX (Open Financial Exchange)
+/// statement. OFX is SGML, not XML, so tags commonly aren't closed; this
+/// reads just far enough to pull `DTPOSTED`, `TRNAMT`, and `NAME`/`PAYEE`
+/// out of each transaction block rather than parsing the document fully.
+pub fn parse_ofx(input: &str) -> Result<Vec<StatementRow>, String> {
+    let mut rows = Vec::new();
+    for block in input.split("<STMTTRN>").skip(1) {
+        let block = block.split("</STMTTRN>").next().unwrap_or(block);
+        let date = ofx_tag(block, "DTPOSTED").ok_or("an OFX transaction is missing DTPOSTED")?;
+        let amount = ofx_tag(block, "TRNAMT").ok_or("an OFX transaction is missing TRNAMT")?;
+        let payee = ofx_tag(block, "NAME").or_else(|| ofx_tag(block, "PAYEE")).unwrap_or_default();
+        rows.push(StatementRow {
+            date: Date::parse(&ofx_date_to_iso(&date)?)?.to_string(),
+            payee,
+            amount: Milliunits::from_decimal_str(&amount)?,
+        });
+    }
+    Ok(rows)
+}
+
+/// Finds an OFX `<TAG>value` entry within `block` and returns its trimmed
+/// value, stopping at the next `<` since OFX tags are typically unclosed.
+fn ofx_tag(block: &str, tag: &str) -> Option<String> {
+    let open = format!("<{tag}>");
+    let start = block.find(&open)? + open.len();
+    let rest = &block[start..];
+    let end = rest.find('<').unwrap_or(rest.len());
+    Some(rest[..end].trim().to_string())
+}
+
+/// Converts an OFX `DTPOSTED` value (`YYYYMMDD`, optionally with a time or
+/// timezone suffix) into the `YYYY-MM-DD` form the rest of the domain uses.
+fn ofx_date_to_iso(s: &str) -> Result<String, String> {
+    if s.len() < 8 {
+        return Err(format!("{s:?} is not a valid OFX date (expected YYYYMMDD...)"));
+    }
+    Ok(format!("{}-{}-{}", &s[0..4], &s[4..6], &s[6..8]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_csv_regardless_of_column_order() {
+        let csv = "payee,amount,date\nCoffee Shop,-4.50,2025-06-01\n";
+        let rows = parse_csv(csv).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].date, "2025-06-01");
+        assert_eq!(rows[0].payee, "Coffee Shop");
+        assert_eq!(rows[0].amount, Milliunits::from_decimal_str("-4.50").unwrap());
+    }
+
+    #[test]
+    fn parses_quoted_payee_containing_a_comma() {
+        let csv = "date,payee,amount\n2025-06-01,\"SMITH, JOHN\",-60.00\n";
+        let rows = parse_csv(csv).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].payee, "SMITH, JOHN");
+    }
+
+    #[test]
+    fn rejects_rows_with_an_unexpected_column_count() {
+        let csv = "date,payee,amount\n2025-06-01,Oops, too, many, commas,-4.50\n";
+        assert!(parse_csv(csv).is_err());
+    }
+
+    #[test]
+    fn parses_ofx_transaction_blocks() {
+        let ofx = "<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250601120000<TRNAMT>-4.50<NAME>Coffee Shop</STMTTRN>";
+        let rows = parse_ofx(ofx).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].date, "2025-06-01");
+        assert_eq!(rows[0].payee, "Coffee Shop");
+        assert_eq!(rows[0].amount, Milliunits::from_decimal_str("-4.50").unwrap());
+    }
+
+    #[test]
+    fn import_id_ignores_payee_whitespace_and_case() {
+        let account_id = Uuid::nil();
+        let amount = Milliunits::from_decimal_str("-4.50").unwrap();
+        let a = import_id(account_id, "2025-06-01", amount, "Coffee  Shop");
+        let b = import_id(account_id, "2025-06-01", amount, "coffee shop");
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn import_id_differs_on_amount() {
+        let account_id = Uuid::nil();
+        let a = import_id(account_id, "2025-06-01", Milliunits::from_decimal_str("-4.50").unwrap(), "Coffee Shop");
+        let b = import_id(account_id, "2025-06-01", Milliunits::from_decimal_str("-4.51").unwrap(), "Coffee Shop");
+        assert_ne!(a, b);
+    }
+}
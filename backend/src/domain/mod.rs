@@ -1,17 +1,34 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+mod date;
+mod import;
+mod money;
+mod month;
+mod scheduled;
+pub use date::Date;
+pub use import::{ImportSummary, StatementRow, parse_csv, parse_ofx};
+pub use money::Milliunits;
+pub use month::Month;
+pub use scheduled::{Frequency, ScheduledTransaction};
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Account {
     pub id: Uuid,
     pub name: String,
-    pub starting_balance: f64,
+    pub starting_balance: Milliunits,
+    /// The `Budget::server_knowledge` value at the time this account was
+    /// created, for delta sync. Set by the server; ignored on input.
+    #[serde(default)]
+    pub last_modified_knowledge: u64,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Category {
     pub id: Uuid,
     pub name: String,
+    #[serde(default)]
+    pub last_modified_knowledge: u64,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -22,9 +39,92 @@ pub struct Transaction {
     pub memo: Option<String>,
     pub account_id: Uuid,
     pub category_id: Option<Uuid>,
-    pub inflow: f64,
-    pub outflow: f64,
+    pub inflow: Milliunits,
+    pub outflow: Milliunits,
     pub status: String,
+    /// A split of this transaction across multiple categories. When
+    /// non-empty, each sub-transaction's net amount is attributed to its own
+    /// category instead of the parent's `category_id`, and the
+    /// sub-transaction amounts must sum to the parent's totals (see
+    /// `Transaction::validate`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtransactions: Vec<SubTransaction>,
+    /// Set when this transaction was created by `Budget::import_statement`,
+    /// to the `import::import_id` of the statement row it came from. Used to
+    /// recognize the same row on a repeat import of the same statement.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_id: Option<String>,
+    #[serde(default)]
+    pub last_modified_knowledge: u64,
+}
+
+/// Criteria for `Budget::filter_transactions`; `None` fields are unconstrained.
+#[derive(Default)]
+pub struct TransactionFilter {
+    pub since_knowledge: Option<u64>,
+    pub since_date: Option<String>,
+    pub account_id: Option<Uuid>,
+    pub category_id: Option<Uuid>,
+    pub status: Option<String>,
+}
+
+/// One category's share of a split `Transaction`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SubTransaction {
+    pub category_id: Option<Uuid>,
+    pub inflow: Milliunits,
+    pub outflow: Milliunits,
+    pub memo: Option<String>,
+}
+
+impl Transaction {
+    /// The net amount (inflow minus outflow) this transaction attributes to
+    /// `cat`, attributing through `subtransactions` when present and falling
+    /// back to the flat `category_id` otherwise.
+    pub fn category_amount(&self, cat: Uuid) -> Milliunits {
+        if self.subtransactions.is_empty() {
+            if self.category_id == Some(cat) {
+                self.inflow - self.outflow
+            } else {
+                Milliunits::ZERO
+            }
+        } else {
+            self.subtransactions
+                .iter()
+                .filter(|s| s.category_id == Some(cat))
+                .map(|s| s.inflow - s.outflow)
+                .sum()
+        }
+    }
+
+    /// Whether this transaction is assigned to `cat` at all, attributing
+    /// through `subtransactions` when present and falling back to the flat
+    /// `category_id` otherwise. Unlike `category_amount`, this is `true` for
+    /// a categorized transaction even if its net amount happens to be zero.
+    pub fn touches_category(&self, cat: Uuid) -> bool {
+        if self.subtransactions.is_empty() {
+            self.category_id == Some(cat)
+        } else {
+            self.subtransactions.iter().any(|s| s.category_id == Some(cat))
+        }
+    }
+
+    /// Checks that, when present, the sub-transaction amounts sum to this
+    /// transaction's totals.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.subtransactions.is_empty() {
+            return Ok(());
+        }
+        let sub_inflow: Milliunits = self.subtransactions.iter().map(|s| s.inflow).sum();
+        let sub_outflow: Milliunits = self.subtransactions.iter().map(|s| s.outflow).sum();
+        if sub_inflow != self.inflow || sub_outflow != self.outflow {
+            return Err(format!(
+                "subtransactions sum to {sub_inflow}/{sub_outflow} but the transaction totals {}/{}",
+                self.inflow, self.outflow
+            ));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -33,8 +133,10 @@ pub struct CategoryTransfer {
     pub date: String,
     pub from_category_id: Uuid,
     pub to_category_id: Uuid,
-    pub amount: f64,
+    pub amount: Milliunits,
     pub memo: Option<String>,
+    #[serde(default)]
+    pub last_modified_knowledge: u64,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -43,8 +145,18 @@ pub struct AccountTransfer {
     pub date: String,
     pub from_account_id: Uuid,
     pub to_account_id: Uuid,
-    pub amount: f64,
+    pub amount: Milliunits,
     pub memo: Option<String>,
+    #[serde(default)]
+    pub last_modified_knowledge: u64,
+}
+
+/// A category's budgeted amount for a single month.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CategoryBudget {
+    pub category_id: Uuid,
+    pub month: Month,
+    pub budgeted: Milliunits,
 }
 
 pub struct Budget {
@@ -55,6 +167,11 @@ pub struct Budget {
     pub transactions: Vec<Transaction>,
     pub category_transfers: Vec<CategoryTransfer>,
     pub account_transfers: Vec<AccountTransfer>,
+    pub category_budgets: Vec<CategoryBudget>,
+    pub scheduled_transactions: Vec<ScheduledTransaction>,
+    /// Monotonically increasing counter stamped onto entities as they're
+    /// created, so clients can sync incrementally via `last_knowledge_of_server`.
+    pub server_knowledge: u64,
 }
 
 impl Default for Budget {
@@ -66,42 +183,281 @@ impl Default for Budget {
             transactions: Vec::new(),
             category_transfers: Vec::new(),
             account_transfers: Vec::new(),
+            category_budgets: Vec::new(),
+            scheduled_transactions: Vec::new(),
+            server_knowledge: 0,
         }
     }
 }
 
 impl Budget {
-    pub fn category_balance(&self, cat: Uuid) -> f64 {
-        let mut balance = 0.0;
-        for tx in self.transactions.iter().filter(|t| t.category_id == Some(cat)) {
-            balance += tx.inflow - tx.outflow;
-        }
-        for tr in &self.category_transfers {
-            if tr.to_category_id == cat {
-                balance += tr.amount;
-            }
-            if tr.from_category_id == cat {
-                balance -= tr.amount;
+    /// Bumps and returns `server_knowledge`, for stamping a newly created entity.
+    fn next_knowledge(&mut self) -> u64 {
+        self.server_knowledge += 1;
+        self.server_knowledge
+    }
+
+    pub fn create_account(&mut self, mut account: Account) -> Account {
+        account.last_modified_knowledge = self.next_knowledge();
+        self.accounts.push(account.clone());
+        account
+    }
+
+    pub fn create_category(&mut self, mut category: Category) -> Category {
+        category.last_modified_knowledge = self.next_knowledge();
+        self.categories.push(category.clone());
+        category
+    }
+
+    pub fn create_transaction(&mut self, mut tx: Transaction) -> Result<Transaction, String> {
+        tx.validate()?;
+        tx.last_modified_knowledge = self.next_knowledge();
+        self.transactions.push(tx.clone());
+        Ok(tx)
+    }
+
+    /// Converts each parsed statement row into a `Transaction` on `account`,
+    /// skipping rows whose `import::import_id` already exists among that
+    /// account's transactions so re-importing the same statement is a no-op.
+    pub fn import_statement(&mut self, account_id: Uuid, rows: Vec<StatementRow>) -> ImportSummary {
+        let mut imported = 0;
+        let mut skipped = 0;
+        for row in rows {
+            let id = import::import_id(account_id, &row.date, row.amount, &row.payee);
+            let already_imported = self
+                .transactions
+                .iter()
+                .any(|t| t.account_id == account_id && t.import_id.as_deref() == Some(id.as_str()));
+            if already_imported {
+                skipped += 1;
+                continue;
             }
+            let (inflow, outflow) = if row.amount >= Milliunits::ZERO {
+                (row.amount, Milliunits::ZERO)
+            } else {
+                (Milliunits::ZERO, -row.amount)
+            };
+            let tx = Transaction {
+                id: Uuid::new_v4(),
+                date: row.date,
+                payee: Some(row.payee),
+                memo: None,
+                account_id,
+                category_id: None,
+                inflow,
+                outflow,
+                status: "unreconciled".into(),
+                subtransactions: Vec::new(),
+                import_id: Some(id),
+                last_modified_knowledge: self.next_knowledge(),
+            };
+            self.transactions.push(tx);
+            imported += 1;
         }
-        balance
+        ImportSummary { imported, skipped }
     }
 
-    pub fn available_to_budget(&self) -> f64 {
-        self.category_balance(self.system_available_category_id)
+    pub fn create_scheduled_transaction(
+        &mut self,
+        mut sched: ScheduledTransaction,
+    ) -> ScheduledTransaction {
+        sched.last_modified_knowledge = self.next_knowledge();
+        self.scheduled_transactions.push(sched.clone());
+        sched
     }
 
-    pub fn account_balance(&self, acc: Uuid) -> f64 {
-        let starting = self
-            .accounts
+    pub fn create_category_transfer(&mut self, mut transfer: CategoryTransfer) -> CategoryTransfer {
+        transfer.last_modified_knowledge = self.next_knowledge();
+        self.category_transfers.push(transfer.clone());
+        transfer
+    }
+
+    pub fn create_account_transfer(&mut self, mut transfer: AccountTransfer) -> AccountTransfer {
+        transfer.last_modified_knowledge = self.next_knowledge();
+        self.account_transfers.push(transfer.clone());
+        transfer
+    }
+
+    /// Funds not yet assigned to a category as of the given month.
+    pub fn available_to_budget(&self, month: Month) -> Milliunits {
+        let assigned: Milliunits = self
+            .category_budgets
+            .iter()
+            .filter(|b| b.category_id != self.system_available_category_id && b.month <= month)
+            .map(|b| b.budgeted)
+            .sum();
+        self.month_category_balance(self.system_available_category_id, month) - assigned
+    }
+
+    /// The amount budgeted into `cat` for `month`, or zero if none was set.
+    pub fn category_budgeted(&self, cat: Uuid, month: Month) -> Milliunits {
+        self.category_budgets
+            .iter()
+            .find(|b| b.category_id == cat && b.month == month)
+            .map(|b| b.budgeted)
+            .unwrap_or(Milliunits::ZERO)
+    }
+
+    /// Sets (or replaces) the budgeted amount for `cat` in `month`.
+    pub fn set_category_budgeted(&mut self, cat: Uuid, month: Month, budgeted: Milliunits) {
+        match self
+            .category_budgets
+            .iter_mut()
+            .find(|b| b.category_id == cat && b.month == month)
+        {
+            Some(existing) => existing.budgeted = budgeted,
+            None => self.category_budgets.push(CategoryBudget { category_id: cat, month, budgeted }),
+        }
+    }
+
+    /// Net inflow minus outflow of `cat`'s transactions dated within `month`,
+    /// plus any `category_transfers` moving funds into or out of `cat` that
+    /// month (a transfer in counts as inflow, a transfer out as outflow) —
+    /// transfers affect a category's balance the same way an equivalent
+    /// transaction would.
+    pub fn month_activity(&self, cat: Uuid, month: Month) -> Milliunits {
+        let transaction_activity: Milliunits = self
+            .transactions
+            .iter()
+            .filter(|t| month.contains_date(&t.date))
+            .map(|t| t.category_amount(cat))
+            .sum();
+        let transfer_activity: Milliunits = self
+            .category_transfers
+            .iter()
+            .filter(|tr| month.contains_date(&tr.date))
+            .map(|tr| {
+                if tr.to_category_id == cat {
+                    tr.amount
+                } else if tr.from_category_id == cat {
+                    -tr.amount
+                } else {
+                    Milliunits::ZERO
+                }
+            })
+            .sum();
+        transaction_activity + transfer_activity
+    }
+
+    /// The earliest month in which `cat` has either a budgeted amount,
+    /// transaction activity, or a category transfer, or `None` if the
+    /// category has never been used.
+    fn earliest_activity_month(&self, cat: Uuid) -> Option<Month> {
+        let budgeted_months = self
+            .category_budgets
             .iter()
-            .find(|a| a.id == acc)
-            .map(|a| a.starting_balance)
-            .unwrap_or(0.0);
-        let mut balance = starting;
-        for tx in self.transactions.iter().filter(|t| t.account_id == acc) {
-            balance += tx.inflow - tx.outflow;
+            .filter(|b| b.category_id == cat)
+            .map(|b| b.month);
+        let transaction_months = self
+            .transactions
+            .iter()
+            .filter(|t| t.category_amount(cat) != Milliunits::ZERO)
+            .filter_map(|t| Month::of_date(&t.date).ok());
+        let transfer_months = self
+            .category_transfers
+            .iter()
+            .filter(|tr| tr.to_category_id == cat || tr.from_category_id == cat)
+            .filter_map(|tr| Month::of_date(&tr.date).ok());
+        budgeted_months.chain(transaction_months).chain(transfer_months).min()
+    }
+
+    /// The ending balance of `cat` for `month`: the previous month's ending
+    /// balance (recursively, starting from zero at the first activity) plus
+    /// this month's budgeted amount plus this month's activity.
+    pub fn month_category_balance(&self, cat: Uuid, month: Month) -> Milliunits {
+        let Some(earliest) = self.earliest_activity_month(cat) else {
+            return Milliunits::ZERO;
+        };
+        if month < earliest {
+            return Milliunits::ZERO;
         }
-        balance
+        let previous = if month == earliest {
+            Milliunits::ZERO
+        } else {
+            self.month_category_balance(cat, month.previous())
+        };
+        previous + self.category_budgeted(cat, month) + self.month_activity(cat, month)
+    }
+
+    /// All months with either a budgeted amount, transaction activity, or a
+    /// category transfer in any category, sorted ascending.
+    pub fn all_months(&self) -> Vec<Month> {
+        let mut months: Vec<Month> = self
+            .category_budgets
+            .iter()
+            .map(|b| b.month)
+            .chain(self.transactions.iter().filter_map(|t| Month::of_date(&t.date).ok()))
+            .chain(self.category_transfers.iter().filter_map(|tr| Month::of_date(&tr.date).ok()))
+            .collect();
+        months.sort();
+        months.dedup();
+        months
+    }
+
+    /// The most recent month with any known activity, used as the default
+    /// when a caller doesn't specify one.
+    pub fn latest_month(&self) -> Option<Month> {
+        self.all_months().into_iter().last()
+    }
+
+    /// Transactions matching `filter`, sorted by `(date, id)` — the stable
+    /// order `paginate_transactions` expects its cursor to resume within.
+    /// `category_id` matches through `Transaction::touches_category`, so a
+    /// split transaction matches on any category it's assigned to, not just
+    /// the parent's flat `category_id`.
+    pub fn filter_transactions(&self, filter: &TransactionFilter) -> Vec<&Transaction> {
+        let mut matching: Vec<&Transaction> = self
+            .transactions
+            .iter()
+            .filter(|t| filter.since_knowledge.is_none_or(|since| t.last_modified_knowledge > since))
+            .filter(|t| filter.since_date.as_deref().is_none_or(|since| t.date.as_str() >= since))
+            .filter(|t| filter.account_id.is_none_or(|id| t.account_id == id))
+            .filter(|t| filter.category_id.is_none_or(|id| t.touches_category(id)))
+            .filter(|t| filter.status.as_deref().is_none_or(|status| t.status == status))
+            .collect();
+        matching.sort_by(|a, b| (a.date.as_str(), a.id).cmp(&(b.date.as_str(), b.id)));
+        matching
+    }
+
+    /// Slices `matching` (as produced by `filter_transactions`) into a page
+    /// of at most `page_size` entries starting strictly after `cursor`,
+    /// returning the page and the `(date, id)` cursor to resume from if more
+    /// remain. `page_size` must be nonzero — an empty page would otherwise
+    /// be indistinguishable from "no more data" to the caller.
+    pub fn paginate_transactions<'a>(
+        matching: &[&'a Transaction],
+        cursor: Option<(&str, Uuid)>,
+        page_size: usize,
+    ) -> (Vec<&'a Transaction>, Option<(String, Uuid)>) {
+        let start = match cursor {
+            Some((date, id)) => matching.partition_point(|t| (t.date.as_str(), t.id) <= (date, id)),
+            None => 0,
+        };
+        let page: Vec<&Transaction> = matching[start..].iter().take(page_size).copied().collect();
+        let next_cursor = if start + page.len() < matching.len() {
+            page.last().map(|t| (t.date.clone(), t.id))
+        } else {
+            None
+        };
+        (page, next_cursor)
+    }
+
+    /// Materializes and pushes the next due occurrence of the scheduled
+    /// transaction `id`, advancing its `last_posted` marker. Returns `None`
+    /// if the schedule doesn't exist or has no occurrence left to post.
+    pub fn post_due_scheduled_transaction(&mut self, id: Uuid) -> Option<Transaction> {
+        let next_date = self
+            .scheduled_transactions
+            .iter()
+            .find(|s| s.id == id)?
+            .next_due()?;
+        let knowledge = self.next_knowledge();
+        let sched = self.scheduled_transactions.iter_mut().find(|s| s.id == id)?;
+        sched.last_posted = Some(next_date);
+        sched.last_modified_knowledge = knowledge;
+        let mut tx = sched.upcoming(next_date, next_date).pop()?;
+        tx.last_modified_knowledge = self.next_knowledge();
+        self.transactions.push(tx.clone());
+        Some(tx)
     }
 }
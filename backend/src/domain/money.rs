@@ -0,0 +1,170 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A signed monetary amount in milliunits (thousandths of the currency unit),
+/// e.g. $12.34 is represented as `12340`. Keeping balances as integers avoids
+/// the float drift that comes from summing decimal amounts (0.1 + 0.2 ≠ 0.3).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Milliunits(pub i64);
+
+impl Milliunits {
+    pub const ZERO: Milliunits = Milliunits(0);
+
+    /// Parses a decimal string such as `"12.34"` or `"-5"` into milliunits.
+    /// The fractional part is padded with trailing zeros up to 3 digits;
+    /// more than 3 fractional digits is rejected rather than truncated.
+    pub fn from_decimal_str(s: &str) -> Result<Milliunits, String> {
+        let s = s.trim();
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (whole, frac) = match unsigned.split_once('.') {
+            Some((whole, frac)) => (whole, frac),
+            None => (unsigned, ""),
+        };
+        if frac.len() > 3 {
+            return Err(format!("{s:?} has more than 3 fractional digits"));
+        }
+        if whole.is_empty() && frac.is_empty() {
+            return Err(format!("{s:?} is not a valid decimal amount"));
+        }
+        if !whole.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("{s:?} has an invalid integer part"));
+        }
+        if !frac.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("{s:?} has an invalid fractional part"));
+        }
+        let whole: i64 = if whole.is_empty() {
+            0
+        } else {
+            whole
+                .parse()
+                .map_err(|_| format!("{s:?} has an invalid integer part"))?
+        };
+        let mut padded_frac = frac.to_string();
+        while padded_frac.len() < 3 {
+            padded_frac.push('0');
+        }
+        let frac: i64 = if padded_frac.is_empty() {
+            0
+        } else {
+            padded_frac
+                .parse()
+                .map_err(|_| format!("{s:?} has an invalid fractional part"))?
+        };
+        let magnitude = whole * 1000 + frac;
+        Ok(Milliunits(if negative { -magnitude } else { magnitude }))
+    }
+
+    /// Renders milliunits back out as a decimal string with exactly 3
+    /// fractional digits, e.g. `12340` -> `"12.340"`.
+    pub fn to_decimal_str(self) -> String {
+        let negative = self.0 < 0;
+        let magnitude = self.0.unsigned_abs();
+        let whole = magnitude / 1000;
+        let frac = magnitude % 1000;
+        format!("{}{whole}.{frac:03}", if negative { "-" } else { "" })
+    }
+}
+
+impl fmt::Display for Milliunits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_decimal_str())
+    }
+}
+
+impl Add for Milliunits {
+    type Output = Milliunits;
+    fn add(self, rhs: Milliunits) -> Milliunits {
+        Milliunits(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Milliunits {
+    type Output = Milliunits;
+    fn sub(self, rhs: Milliunits) -> Milliunits {
+        Milliunits(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Milliunits {
+    type Output = Milliunits;
+    fn neg(self) -> Milliunits {
+        Milliunits(-self.0)
+    }
+}
+
+impl AddAssign for Milliunits {
+    fn add_assign(&mut self, rhs: Milliunits) {
+        self.0 += rhs.0;
+    }
+}
+
+impl SubAssign for Milliunits {
+    fn sub_assign(&mut self, rhs: Milliunits) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Sum for Milliunits {
+    fn sum<I: Iterator<Item = Milliunits>>(iter: I) -> Milliunits {
+        iter.fold(Milliunits::ZERO, Add::add)
+    }
+}
+
+impl Serialize for Milliunits {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Milliunits {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Integer(i64),
+            Decimal(String),
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Integer(n) => Ok(Milliunits(n)),
+            Repr::Decimal(s) => {
+                Milliunits::from_decimal_str(&s).map_err(serde::de::Error::custom)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Milliunits;
+
+    #[test]
+    fn parses_decimal_strings() {
+        assert_eq!(Milliunits::from_decimal_str("12.34").unwrap(), Milliunits(12340));
+        assert_eq!(Milliunits::from_decimal_str("-5").unwrap(), Milliunits(-5000));
+        assert_eq!(Milliunits::from_decimal_str("0.1").unwrap(), Milliunits(100));
+        assert_eq!(Milliunits::from_decimal_str("-0.005").unwrap(), Milliunits(-5));
+    }
+
+    #[test]
+    fn rejects_extra_fractional_digits() {
+        assert!(Milliunits::from_decimal_str("1.2345").is_err());
+    }
+
+    #[test]
+    fn rejects_a_doubled_sign() {
+        assert!(Milliunits::from_decimal_str("--5").is_err());
+        assert!(Milliunits::from_decimal_str("5.-3").is_err());
+    }
+
+    #[test]
+    fn round_trips_through_decimal_str() {
+        let amount = Milliunits::from_decimal_str("-12.34").unwrap();
+        assert_eq!(amount.to_decimal_str(), "-12.340");
+    }
+}
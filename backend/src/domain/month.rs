@@ -0,0 +1,92 @@
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A calendar month, keyed as `YYYY-MM`, used to bucket budgeted amounts and
+/// transaction activity for the envelope-budgeting rollover.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Month {
+    pub year: i32,
+    pub month: u32,
+}
+
+impl Month {
+    /// Parses a `"YYYY-MM"` string.
+    pub fn parse(s: &str) -> Result<Month, String> {
+        let (year, month) = s
+            .split_once('-')
+            .ok_or_else(|| format!("{s:?} is not a valid month (expected YYYY-MM)"))?;
+        let year: i32 = year
+            .parse()
+            .map_err(|_| format!("{s:?} has an invalid year"))?;
+        let month: u32 = month
+            .parse()
+            .map_err(|_| format!("{s:?} has an invalid month"))?;
+        if !(1..=12).contains(&month) {
+            return Err(format!("{s:?} has a month outside 1-12"));
+        }
+        Ok(Month { year, month })
+    }
+
+    /// Extracts the month containing an ISO `YYYY-MM-DD` date.
+    pub fn of_date(date: &str) -> Result<Month, String> {
+        if date.len() < 7 {
+            return Err(format!("{date:?} is not a valid ISO date"));
+        }
+        Month::parse(&date[0..7])
+    }
+
+    pub fn contains_date(&self, date: &str) -> bool {
+        Month::of_date(date).map(|m| m == *self).unwrap_or(false)
+    }
+
+    /// The month immediately before this one.
+    pub fn previous(&self) -> Month {
+        if self.month == 1 {
+            Month { year: self.year - 1, month: 12 }
+        } else {
+            Month { year: self.year, month: self.month - 1 }
+        }
+    }
+}
+
+impl fmt::Display for Month {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+impl Serialize for Month {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Month {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Month::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Month;
+
+    #[test]
+    fn parses_and_displays() {
+        let m = Month::parse("2025-06").unwrap();
+        assert_eq!(m.to_string(), "2025-06");
+    }
+
+    #[test]
+    fn steps_back_across_year_boundary() {
+        let jan = Month::parse("2025-01").unwrap();
+        assert_eq!(jan.previous(), Month::parse("2024-12").unwrap());
+    }
+
+    #[test]
+    fn extracts_month_from_date() {
+        assert_eq!(Month::of_date("2025-06-20").unwrap(), Month::parse("2025-06").unwrap());
+    }
+}
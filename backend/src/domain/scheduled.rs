@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{Date, Milliunits, Transaction};
+
+/// How often a `ScheduledTransaction` recurs, mirroring the recurrence
+/// options exposed by mature budgeting APIs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    EveryOtherWeek,
+    Monthly,
+    Yearly,
+    Never,
+}
+
+impl Frequency {
+    /// The date of the `n`-th occurrence (0-indexed) starting from `first`.
+    fn nth_occurrence(self, first: Date, n: i64) -> Date {
+        match self {
+            Frequency::Daily => first.add_days(n),
+            Frequency::Weekly => first.add_days(n * 7),
+            Frequency::EveryOtherWeek => first.add_days(n * 14),
+            Frequency::Monthly => first.add_months(n),
+            Frequency::Yearly => first.add_years(n),
+            Frequency::Never => first,
+        }
+    }
+}
+
+/// A recurring transaction template, e.g. a monthly rent payment. Materializes
+/// into real `Transaction`s either for preview (`upcoming`) or by posting the
+/// next due occurrence (`Budget::post_due_scheduled_transaction`).
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScheduledTransaction {
+    pub id: Uuid,
+    pub date_first: Date,
+    pub frequency: Frequency,
+    pub account_id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub payee: Option<String>,
+    pub inflow: Milliunits,
+    pub outflow: Milliunits,
+    pub memo: Option<String>,
+    /// The date of the most recently posted occurrence, if any. Advances
+    /// each time `/scheduled-transactions/:id/post` materializes one, so
+    /// repeated posts walk forward through the schedule instead of
+    /// re-posting the same occurrence.
+    #[serde(default)]
+    pub last_posted: Option<Date>,
+    #[serde(default)]
+    pub last_modified_knowledge: u64,
+}
+
+impl ScheduledTransaction {
+    /// Every occurrence of this schedule landing in `[from, to]`, inclusive,
+    /// materialized as `Transaction`s.
+    pub fn upcoming(&self, from: Date, to: Date) -> Vec<Transaction> {
+        if self.date_first > to {
+            return Vec::new();
+        }
+        if self.frequency == Frequency::Never {
+            return if self.date_first >= from {
+                vec![self.materialize(self.date_first)]
+            } else {
+                Vec::new()
+            };
+        }
+        let mut occurrences = Vec::new();
+        let mut n = 0;
+        loop {
+            let date = self.frequency.nth_occurrence(self.date_first, n);
+            if date > to {
+                break;
+            }
+            if date >= from {
+                occurrences.push(self.materialize(date));
+            }
+            n += 1;
+        }
+        occurrences
+    }
+
+    /// The date of the next occurrence that hasn't been posted yet, or
+    /// `None` if the schedule is exhausted (a `Never` schedule that has
+    /// already posted its one occurrence).
+    pub fn next_due(&self) -> Option<Date> {
+        let last_posted = match self.last_posted {
+            None => return Some(self.date_first),
+            Some(last_posted) => last_posted,
+        };
+        if self.frequency == Frequency::Never {
+            return None;
+        }
+        let mut n = 1;
+        loop {
+            let date = self.frequency.nth_occurrence(self.date_first, n);
+            if date > last_posted {
+                return Some(date);
+            }
+            n += 1;
+        }
+    }
+
+    fn materialize(&self, date: Date) -> Transaction {
+        Transaction {
+            id: Uuid::new_v4(),
+            date: date.to_string(),
+            payee: self.payee.clone(),
+            memo: self.memo.clone(),
+            account_id: self.account_id,
+            category_id: self.category_id,
+            inflow: self.inflow,
+            outflow: self.outflow,
+            status: "scheduled".into(),
+            subtransactions: Vec::new(),
+            import_id: None,
+            last_modified_knowledge: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(frequency: Frequency, date_first: &str) -> ScheduledTransaction {
+        ScheduledTransaction {
+            id: Uuid::new_v4(),
+            date_first: Date::parse(date_first).unwrap(),
+            frequency,
+            account_id: Uuid::new_v4(),
+            category_id: None,
+            payee: Some("Landlord".into()),
+            inflow: Milliunits::ZERO,
+            outflow: Milliunits::from_decimal_str("1200.00").unwrap(),
+            memo: None,
+            last_posted: None,
+            last_modified_knowledge: 0,
+        }
+    }
+
+    #[test]
+    fn monthly_upcoming_clamps_short_months() {
+        let sched = schedule(Frequency::Monthly, "2025-01-31");
+        let dates: Vec<String> = sched
+            .upcoming(Date::parse("2025-01-01").unwrap(), Date::parse("2025-04-01").unwrap())
+            .into_iter()
+            .map(|t| t.date)
+            .collect();
+        assert_eq!(dates, vec!["2025-01-31", "2025-02-28", "2025-03-31"]);
+    }
+
+    #[test]
+    fn never_emits_at_most_one_occurrence() {
+        let sched = schedule(Frequency::Never, "2025-06-01");
+        let in_window = sched.upcoming(Date::parse("2025-01-01").unwrap(), Date::parse("2025-12-31").unwrap());
+        assert_eq!(in_window.len(), 1);
+        let out_of_window = sched.upcoming(Date::parse("2025-07-01").unwrap(), Date::parse("2025-12-31").unwrap());
+        assert!(out_of_window.is_empty());
+    }
+
+    #[test]
+    fn next_due_advances_after_posting() {
+        let mut sched = schedule(Frequency::Weekly, "2025-06-01");
+        assert_eq!(sched.next_due(), Some(Date::parse("2025-06-01").unwrap()));
+        sched.last_posted = Some(Date::parse("2025-06-01").unwrap());
+        assert_eq!(sched.next_due(), Some(Date::parse("2025-06-08").unwrap()));
+    }
+}
@@ -1,5 +1,7 @@
 use axum::{
     Extension, Json, Router,
+    extract::{Path, Query},
+    http::StatusCode,
     routing::{get, post},
 };
 use std::sync::{Arc, Mutex};
@@ -7,16 +9,124 @@ use tokio::net::TcpListener;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use uuid::Uuid;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 mod domain;
-use domain::{Account, AccountTransfer, Budget, Category, CategoryTransfer, Transaction};
+use domain::{
+    Account, AccountTransfer, Budget, Category, CategoryTransfer, ImportSummary, Milliunits,
+    Month, ScheduledTransaction, Transaction, TransactionFilter, parse_csv, parse_ofx,
+};
 
 #[derive(Serialize)]
 struct Dashboard {
+    /// Rendered with `Milliunits::to_decimal_str`'s full 3 decimal places
+    /// (not the 2-decimal display precision of a currency amount), since
+    /// milliunits are the domain's unit of record and truncating here would
+    /// lose the sub-cent precision `/available` exposes as a raw integer.
     available_to_budget: String,
 }
 
+#[derive(Deserialize)]
+struct MonthQuery {
+    month: Option<String>,
+}
+
+/// Resolves a caller-supplied `?month=YYYY-MM` to a `Month`, falling back to
+/// the most recent month with any known activity.
+fn resolve_month(budget: &Budget, month: Option<String>) -> Result<Month, StatusCode> {
+    match month {
+        Some(s) => Month::parse(&s).map_err(|_| StatusCode::BAD_REQUEST),
+        None => budget.latest_month().ok_or(StatusCode::BAD_REQUEST),
+    }
+}
+
+#[derive(Serialize)]
+struct CategoryMonthSummary {
+    category_id: Uuid,
+    budgeted: Milliunits,
+    activity: Milliunits,
+    balance: Milliunits,
+}
+
+impl CategoryMonthSummary {
+    fn compute(budget: &Budget, category_id: Uuid, month: Month) -> CategoryMonthSummary {
+        CategoryMonthSummary {
+            category_id,
+            budgeted: budget.category_budgeted(category_id, month),
+            activity: budget.month_activity(category_id, month),
+            balance: budget.month_category_balance(category_id, month),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SetBudgetedRequest {
+    budgeted: Milliunits,
+}
+
+#[derive(Deserialize)]
+struct SyncQuery {
+    last_knowledge_of_server: Option<u64>,
+}
+
+/// Envelope for delta-sync list endpoints: the current server knowledge plus
+/// only the entities stamped after `last_knowledge_of_server`.
+#[derive(Serialize)]
+struct SyncPage<T> {
+    server_knowledge: u64,
+    data: Vec<T>,
+}
+
+impl<T: Clone> SyncPage<T> {
+    fn of(budget: &Budget, all: &[T], since: u64, stamp: impl Fn(&T) -> u64) -> SyncPage<T> {
+        SyncPage {
+            server_knowledge: budget.server_knowledge,
+            data: all.iter().filter(|item| stamp(item) > since).cloned().collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ListTransactionsQuery {
+    last_knowledge_of_server: Option<u64>,
+    since: Option<String>,
+    account_id: Option<Uuid>,
+    category_id: Option<Uuid>,
+    status: Option<String>,
+    page_size: Option<usize>,
+    cursor: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TransactionPage {
+    server_knowledge: u64,
+    data: Vec<Transaction>,
+    next_cursor: Option<String>,
+}
+
+fn encode_cursor(date: &str, id: Uuid) -> String {
+    format!("{date}|{id}")
+}
+
+fn decode_cursor(cursor: &str) -> Result<(String, Uuid), StatusCode> {
+    let (date, id) = cursor.split_once('|').ok_or(StatusCode::BAD_REQUEST)?;
+    let id: Uuid = id.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+    Ok((date.to_string(), id))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum StatementFormat {
+    Csv,
+    Ofx,
+}
+
+#[derive(Deserialize)]
+struct ImportStatementRequest {
+    format: StatementFormat,
+    data: String,
+}
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::registry()
@@ -44,6 +154,19 @@ async fn main() {
         .route("/account-transfers", get(list_account_transfers))
         .route("/dashboard", get(get_dashboard))
         .route("/available", get(get_available))
+        .route("/months", get(list_months))
+        .route("/months/:month", get(get_month))
+        .route(
+            "/months/:month/categories/:id/budgeted",
+            post(set_category_budgeted),
+        )
+        .route("/scheduled-transactions", post(create_scheduled_transaction))
+        .route("/scheduled-transactions", get(list_scheduled_transactions))
+        .route(
+            "/scheduled-transactions/:id/post",
+            post(post_scheduled_transaction),
+        )
+        .route("/accounts/:id/import", post(import_statement))
         .layer(Extension(state));
 
     let listener = TcpListener::bind("0.0.0.0:3000").await.unwrap();
@@ -54,82 +177,233 @@ async fn main() {
 async fn create_account(
     Extension(state): Extension<Arc<Mutex<Budget>>>,
     Json(payload): Json<Account>,
-) {
+) -> Json<Account> {
     let mut budget = state.lock().unwrap();
-    budget.accounts.push(payload);
+    Json(budget.create_account(payload))
 }
 
-async fn list_accounts(Extension(state): Extension<Arc<Mutex<Budget>>>) -> Json<Vec<Account>> {
+async fn list_accounts(
+    Extension(state): Extension<Arc<Mutex<Budget>>>,
+    Query(q): Query<SyncQuery>,
+) -> Json<SyncPage<Account>> {
     let budget = state.lock().unwrap();
-    Json(budget.accounts.clone())
+    Json(SyncPage::of(
+        &budget,
+        &budget.accounts,
+        q.last_knowledge_of_server.unwrap_or(0),
+        |a| a.last_modified_knowledge,
+    ))
 }
 
 async fn create_category(
     Extension(state): Extension<Arc<Mutex<Budget>>>,
     Json(payload): Json<Category>,
-) {
+) -> Json<Category> {
     let mut budget = state.lock().unwrap();
-    budget.categories.push(payload);
+    Json(budget.create_category(payload))
 }
 
-async fn list_categories(Extension(state): Extension<Arc<Mutex<Budget>>>) -> Json<Vec<Category>> {
+async fn list_categories(
+    Extension(state): Extension<Arc<Mutex<Budget>>>,
+    Query(q): Query<SyncQuery>,
+) -> Json<SyncPage<Category>> {
     let budget = state.lock().unwrap();
-    Json(budget.categories.clone())
+    Json(SyncPage::of(
+        &budget,
+        &budget.categories,
+        q.last_knowledge_of_server.unwrap_or(0),
+        |c| c.last_modified_knowledge,
+    ))
 }
 
 async fn create_transaction(
     Extension(state): Extension<Arc<Mutex<Budget>>>,
     Json(payload): Json<Transaction>,
-) {
+) -> Result<Json<Transaction>, StatusCode> {
     let mut budget = state.lock().unwrap();
-    budget.transactions.push(payload);
+    budget
+        .create_transaction(payload)
+        .map(Json)
+        .map_err(|_| StatusCode::BAD_REQUEST)
 }
 
+/// Lists transactions with optional filtering and keyset pagination.
+///
+/// Results are sorted by `(date, id)`; `cursor` (as returned in a previous
+/// response's `next_cursor`) resumes strictly after that `(date, id)` pair.
 async fn list_transactions(
     Extension(state): Extension<Arc<Mutex<Budget>>>,
-) -> Json<Vec<Transaction>> {
+    Query(q): Query<ListTransactionsQuery>,
+) -> Result<Json<TransactionPage>, StatusCode> {
     let budget = state.lock().unwrap();
-    Json(budget.transactions.clone())
+    let page_size = q.page_size.unwrap_or(100);
+    if page_size == 0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let cursor = q.cursor.as_deref().map(decode_cursor).transpose()?;
+
+    let filter = TransactionFilter {
+        since_knowledge: q.last_knowledge_of_server,
+        since_date: q.since,
+        account_id: q.account_id,
+        category_id: q.category_id,
+        status: q.status,
+    };
+    let matching = budget.filter_transactions(&filter);
+    let (page, next_cursor) = Budget::paginate_transactions(
+        &matching,
+        cursor.as_ref().map(|(date, id)| (date.as_str(), *id)),
+        page_size,
+    );
+
+    Ok(Json(TransactionPage {
+        server_knowledge: budget.server_knowledge,
+        data: page.into_iter().cloned().collect(),
+        next_cursor: next_cursor.map(|(date, id)| encode_cursor(&date, id)),
+    }))
 }
 
 async fn create_category_transfer(
     Extension(state): Extension<Arc<Mutex<Budget>>>,
     Json(payload): Json<CategoryTransfer>,
-) {
+) -> Json<CategoryTransfer> {
     let mut budget = state.lock().unwrap();
-    budget.category_transfers.push(payload);
+    Json(budget.create_category_transfer(payload))
 }
 
 async fn list_category_transfers(
     Extension(state): Extension<Arc<Mutex<Budget>>>,
-) -> Json<Vec<CategoryTransfer>> {
+    Query(q): Query<SyncQuery>,
+) -> Json<SyncPage<CategoryTransfer>> {
     let budget = state.lock().unwrap();
-    Json(budget.category_transfers.clone())
+    Json(SyncPage::of(
+        &budget,
+        &budget.category_transfers,
+        q.last_knowledge_of_server.unwrap_or(0),
+        |t| t.last_modified_knowledge,
+    ))
 }
 
 async fn create_account_transfer(
     Extension(state): Extension<Arc<Mutex<Budget>>>,
     Json(payload): Json<AccountTransfer>,
-) {
+) -> Json<AccountTransfer> {
     let mut budget = state.lock().unwrap();
-    budget.account_transfers.push(payload);
+    Json(budget.create_account_transfer(payload))
 }
 
 async fn list_account_transfers(
     Extension(state): Extension<Arc<Mutex<Budget>>>,
-) -> Json<Vec<AccountTransfer>> {
+    Query(q): Query<SyncQuery>,
+) -> Json<SyncPage<AccountTransfer>> {
     let budget = state.lock().unwrap();
-    Json(budget.account_transfers.clone())
+    Json(SyncPage::of(
+        &budget,
+        &budget.account_transfers,
+        q.last_knowledge_of_server.unwrap_or(0),
+        |t| t.last_modified_knowledge,
+    ))
 }
 
-async fn get_dashboard(Extension(state): Extension<Arc<Mutex<Budget>>>) -> Json<Dashboard> {
+async fn get_dashboard(
+    Extension(state): Extension<Arc<Mutex<Budget>>>,
+    Query(q): Query<MonthQuery>,
+) -> Result<Json<Dashboard>, StatusCode> {
     let budget = state.lock().unwrap();
-    Json(Dashboard {
-        available_to_budget: format!("{:.2}", budget.available_to_budget()),
-    })
+    let month = resolve_month(&budget, q.month)?;
+    Ok(Json(Dashboard {
+        available_to_budget: budget.available_to_budget(month).to_decimal_str(),
+    }))
 }
 
-async fn get_available(Extension(state): Extension<Arc<Mutex<Budget>>>) -> Json<f64> {
+async fn get_available(
+    Extension(state): Extension<Arc<Mutex<Budget>>>,
+    Query(q): Query<MonthQuery>,
+) -> Result<Json<Milliunits>, StatusCode> {
     let budget = state.lock().unwrap();
-    Json(budget.available_to_budget())
+    let month = resolve_month(&budget, q.month)?;
+    Ok(Json(budget.available_to_budget(month)))
+}
+
+async fn list_months(Extension(state): Extension<Arc<Mutex<Budget>>>) -> Json<Vec<Month>> {
+    let budget = state.lock().unwrap();
+    Json(budget.all_months())
+}
+
+async fn get_month(
+    Extension(state): Extension<Arc<Mutex<Budget>>>,
+    Path(month): Path<String>,
+) -> Result<Json<Vec<CategoryMonthSummary>>, StatusCode> {
+    let month = Month::parse(&month).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let budget = state.lock().unwrap();
+    let summaries = budget
+        .categories
+        .iter()
+        .map(|c| CategoryMonthSummary::compute(&budget, c.id, month))
+        .collect();
+    Ok(Json(summaries))
+}
+
+async fn set_category_budgeted(
+    Extension(state): Extension<Arc<Mutex<Budget>>>,
+    Path((month, category_id)): Path<(String, Uuid)>,
+    Json(payload): Json<SetBudgetedRequest>,
+) -> Result<Json<CategoryMonthSummary>, StatusCode> {
+    let month = Month::parse(&month).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let mut budget = state.lock().unwrap();
+    budget.set_category_budgeted(category_id, month, payload.budgeted);
+    Ok(Json(CategoryMonthSummary::compute(&budget, category_id, month)))
+}
+
+async fn create_scheduled_transaction(
+    Extension(state): Extension<Arc<Mutex<Budget>>>,
+    Json(payload): Json<ScheduledTransaction>,
+) -> Json<ScheduledTransaction> {
+    let mut budget = state.lock().unwrap();
+    Json(budget.create_scheduled_transaction(payload))
+}
+
+async fn list_scheduled_transactions(
+    Extension(state): Extension<Arc<Mutex<Budget>>>,
+    Query(q): Query<SyncQuery>,
+) -> Json<SyncPage<ScheduledTransaction>> {
+    let budget = state.lock().unwrap();
+    Json(SyncPage::of(
+        &budget,
+        &budget.scheduled_transactions,
+        q.last_knowledge_of_server.unwrap_or(0),
+        |s| s.last_modified_knowledge,
+    ))
+}
+
+async fn post_scheduled_transaction(
+    Extension(state): Extension<Arc<Mutex<Budget>>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Transaction>, StatusCode> {
+    let mut budget = state.lock().unwrap();
+    budget
+        .post_due_scheduled_transaction(id)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Imports a bank statement onto an account, turning each row into an
+/// `unreconciled` transaction and skipping rows already imported (see
+/// `Budget::import_statement`).
+async fn import_statement(
+    Extension(state): Extension<Arc<Mutex<Budget>>>,
+    Path(account_id): Path<Uuid>,
+    Json(payload): Json<ImportStatementRequest>,
+) -> Result<Json<ImportSummary>, StatusCode> {
+    let rows = match payload.format {
+        StatementFormat::Csv => parse_csv(&payload.data),
+        StatementFormat::Ofx => parse_ofx(&payload.data),
+    }
+    .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let mut budget = state.lock().unwrap();
+    if !budget.accounts.iter().any(|a| a.id == account_id) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(budget.import_statement(account_id, rows)))
 }
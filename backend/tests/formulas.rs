@@ -1,17 +1,37 @@
-use dojo_backend::domain::{Account, Budget, Category, CategoryTransfer, Transaction};
+use dojo_backend::domain::{
+    Account, Budget, Category, CategoryTransfer, Date, Frequency, Milliunits, Month,
+    ScheduledTransaction, SubTransaction, Transaction, TransactionFilter, parse_csv,
+};
 use uuid::Uuid;
 
+fn transaction(date: &str, account_id: Uuid, category_id: Option<Uuid>, status: &str) -> Transaction {
+    Transaction {
+        id: Uuid::new_v4(),
+        date: date.into(),
+        payee: None,
+        memo: None,
+        account_id,
+        category_id,
+        inflow: Milliunits::ZERO,
+        outflow: Milliunits::from_decimal_str("10.00").unwrap(),
+        status: status.into(),
+        subtransactions: Vec::new(),
+        import_id: None,
+        last_modified_knowledge: 0,
+    }
+}
+
 #[test]
-fn category_and_account_balance() {
+fn category_transfer_counts_toward_month_activity_and_available() {
     let available_id = Uuid::new_v4();
     let account_id = Uuid::new_v4();
     let cat_id = Uuid::new_v4();
+    let june = Month::parse("2025-06").unwrap();
 
     let mut budget = Budget::default();
     budget.system_available_category_id = available_id;
-    budget.accounts.push(Account { id: account_id, name: "Checking".into(), starting_balance: 100.0 });
-    budget.categories.push(Category { id: available_id, name: "Available".into() });
-    budget.categories.push(Category { id: cat_id, name: "Groceries".into() });
+    budget.categories.push(Category { id: available_id, name: "Available".into(), last_modified_knowledge: 0 });
+    budget.categories.push(Category { id: cat_id, name: "Groceries".into(), last_modified_knowledge: 0 });
 
     budget.transactions.push(Transaction {
         id: Uuid::new_v4(),
@@ -20,9 +40,12 @@ fn category_and_account_balance() {
         memo: None,
         account_id,
         category_id: Some(cat_id),
-        inflow: 0.0,
-        outflow: 20.0,
+        inflow: Milliunits::ZERO,
+        outflow: Milliunits::from_decimal_str("20.00").unwrap(),
         status: "settled".into(),
+        subtransactions: Vec::new(),
+        import_id: None,
+        last_modified_knowledge: 0,
     });
 
     budget.category_transfers.push(CategoryTransfer {
@@ -30,12 +53,335 @@ fn category_and_account_balance() {
         date: "2025-06-20".into(),
         from_category_id: available_id,
         to_category_id: cat_id,
-        amount: 50.0,
+        amount: Milliunits::from_decimal_str("50.00").unwrap(),
+        memo: None,
+        last_modified_knowledge: 0,
+    });
+
+    // $50 transferred in, $20 spent -> $30 left in the category.
+    assert_eq!(budget.month_category_balance(cat_id, june), Milliunits::from_decimal_str("30.00").unwrap());
+    // The same $50 leaves the available-to-budget category.
+    assert_eq!(budget.available_to_budget(june), Milliunits::from_decimal_str("-50.00").unwrap());
+}
+
+#[test]
+fn all_months_includes_a_month_with_only_a_category_transfer() {
+    let cat_id = Uuid::new_v4();
+    let other_id = Uuid::new_v4();
+
+    let mut budget = Budget::default();
+    budget.category_transfers.push(CategoryTransfer {
+        id: Uuid::new_v4(),
+        date: "2025-07-15".into(),
+        from_category_id: other_id,
+        to_category_id: cat_id,
+        amount: Milliunits::from_decimal_str("50.00").unwrap(),
+        memo: None,
+        last_modified_knowledge: 0,
+    });
+
+    assert_eq!(budget.all_months(), vec![Month::parse("2025-07").unwrap()]);
+}
+
+#[test]
+fn milliunits_decimal_round_trip() {
+    let amount = Milliunits::from_decimal_str("12.34").unwrap();
+    assert_eq!(amount, Milliunits(12340));
+    assert_eq!(amount.to_decimal_str(), "12.340");
+}
+
+#[test]
+fn month_category_balance_rolls_over_leftover() {
+    let cat_id = Uuid::new_v4();
+    let may = Month::parse("2025-05").unwrap();
+    let june = Month::parse("2025-06").unwrap();
+
+    let mut budget = Budget::default();
+    budget.categories.push(Category { id: cat_id, name: "Groceries".into(), last_modified_knowledge: 0 });
+
+    // Budgeted $100 in May, spent $40 -> $60 left over into June.
+    budget.set_category_budgeted(cat_id, may, Milliunits::from_decimal_str("100.00").unwrap());
+    budget.transactions.push(Transaction {
+        id: Uuid::new_v4(),
+        date: "2025-05-10".into(),
+        payee: None,
+        memo: None,
+        account_id: Uuid::new_v4(),
+        category_id: Some(cat_id),
+        inflow: Milliunits::ZERO,
+        outflow: Milliunits::from_decimal_str("40.00").unwrap(),
+        status: "settled".into(),
+        subtransactions: Vec::new(),
+        import_id: None,
+        last_modified_knowledge: 0,
+    });
+    // June budgets another $25 with no spending yet.
+    budget.set_category_budgeted(cat_id, june, Milliunits::from_decimal_str("25.00").unwrap());
+
+    assert_eq!(
+        budget.month_category_balance(cat_id, may),
+        Milliunits::from_decimal_str("60.00").unwrap()
+    );
+    assert_eq!(
+        budget.month_category_balance(cat_id, june),
+        Milliunits::from_decimal_str("85.00").unwrap()
+    );
+    // A month before any activity has a zero balance rather than recursing forever.
+    assert_eq!(
+        budget.month_category_balance(cat_id, Month::parse("2024-01").unwrap()),
+        Milliunits::ZERO
+    );
+}
+
+#[test]
+fn split_transaction_attributes_to_each_subcategory() {
+    let groceries_id = Uuid::new_v4();
+    let household_id = Uuid::new_v4();
+    let account_id = Uuid::new_v4();
+
+    let mut budget = Budget::default();
+    budget.categories.push(Category { id: groceries_id, name: "Groceries".into(), last_modified_knowledge: 0 });
+    budget.categories.push(Category { id: household_id, name: "Household".into(), last_modified_knowledge: 0 });
+
+    budget.transactions.push(Transaction {
+        id: Uuid::new_v4(),
+        date: "2025-06-20".into(),
+        payee: Some("Big Box Store".into()),
+        memo: None,
+        account_id,
+        category_id: None,
+        inflow: Milliunits::ZERO,
+        outflow: Milliunits::from_decimal_str("75.00").unwrap(),
+        status: "settled".into(),
+        subtransactions: vec![
+            SubTransaction {
+                category_id: Some(groceries_id),
+                inflow: Milliunits::ZERO,
+                outflow: Milliunits::from_decimal_str("50.00").unwrap(),
+                memo: None,
+            },
+            SubTransaction {
+                category_id: Some(household_id),
+                inflow: Milliunits::ZERO,
+                outflow: Milliunits::from_decimal_str("25.00").unwrap(),
+                memo: None,
+            },
+        ],
+        import_id: None,
+        last_modified_knowledge: 0,
+    });
+
+    let june = Month::parse("2025-06").unwrap();
+    assert_eq!(
+        budget.month_category_balance(groceries_id, june),
+        Milliunits::from_decimal_str("-50.00").unwrap()
+    );
+    assert_eq!(
+        budget.month_category_balance(household_id, june),
+        Milliunits::from_decimal_str("-25.00").unwrap()
+    );
+}
+
+#[test]
+fn transaction_validate_rejects_mismatched_subtransaction_sum() {
+    let tx = Transaction {
+        id: Uuid::new_v4(),
+        date: "2025-06-20".into(),
+        payee: None,
+        memo: None,
+        account_id: Uuid::new_v4(),
+        category_id: None,
+        inflow: Milliunits::ZERO,
+        outflow: Milliunits::from_decimal_str("75.00").unwrap(),
+        status: "settled".into(),
+        subtransactions: vec![SubTransaction {
+            category_id: Some(Uuid::new_v4()),
+            inflow: Milliunits::ZERO,
+            outflow: Milliunits::from_decimal_str("50.00").unwrap(),
+            memo: None,
+        }],
+        import_id: None,
+        last_modified_knowledge: 0,
+    };
+
+    assert!(tx.validate().is_err());
+}
+
+#[test]
+fn create_account_stamps_server_knowledge() {
+    let mut budget = Budget::default();
+    assert_eq!(budget.server_knowledge, 0);
+
+    let first = budget.create_account(Account {
+        id: Uuid::new_v4(),
+        name: "Checking".into(),
+        starting_balance: Milliunits::ZERO,
+        last_modified_knowledge: 0,
+    });
+    let second = budget.create_account(Account {
+        id: Uuid::new_v4(),
+        name: "Savings".into(),
+        starting_balance: Milliunits::ZERO,
+        last_modified_knowledge: 0,
+    });
+
+    assert_eq!(first.last_modified_knowledge, 1);
+    assert_eq!(second.last_modified_knowledge, 2);
+    assert_eq!(budget.server_knowledge, 2);
+}
+
+#[test]
+fn import_statement_skips_rows_already_imported() {
+    let account_id = Uuid::new_v4();
+    let csv = "date,payee,amount\n2025-06-01,Coffee Shop,-4.50\n2025-06-02,Grocery Store,-60.00\n";
+
+    let mut budget = Budget::default();
+    let first_pass = budget.import_statement(account_id, parse_csv(csv).unwrap());
+    assert_eq!(first_pass.imported, 2);
+    assert_eq!(first_pass.skipped, 0);
+    assert_eq!(budget.transactions.len(), 2);
+
+    // Re-importing the same statement should import nothing new.
+    let second_pass = budget.import_statement(account_id, parse_csv(csv).unwrap());
+    assert_eq!(second_pass.imported, 0);
+    assert_eq!(second_pass.skipped, 2);
+    assert_eq!(budget.transactions.len(), 2);
+
+    let coffee = budget.transactions.iter().find(|t| t.payee.as_deref() == Some("Coffee Shop")).unwrap();
+    assert_eq!(coffee.status, "unreconciled");
+    assert_eq!(coffee.outflow, Milliunits::from_decimal_str("4.50").unwrap());
+}
+
+#[test]
+fn filter_transactions_matches_split_category_through_subtransactions() {
+    let account_id = Uuid::new_v4();
+    let groceries_id = Uuid::new_v4();
+    let household_id = Uuid::new_v4();
+
+    let mut budget = Budget::default();
+    let mut split = transaction("2025-06-20", account_id, None, "settled");
+    split.outflow = Milliunits::from_decimal_str("75.00").unwrap();
+    split.subtransactions = vec![
+        SubTransaction {
+            category_id: Some(groceries_id),
+            inflow: Milliunits::ZERO,
+            outflow: Milliunits::from_decimal_str("50.00").unwrap(),
+            memo: None,
+        },
+        SubTransaction {
+            category_id: Some(household_id),
+            inflow: Milliunits::ZERO,
+            outflow: Milliunits::from_decimal_str("25.00").unwrap(),
+            memo: None,
+        },
+    ];
+    budget.transactions.push(split);
+
+    let filter = TransactionFilter { category_id: Some(household_id), ..Default::default() };
+    let matching = budget.filter_transactions(&filter);
+    assert_eq!(matching.len(), 1);
+
+    let unrelated_category = Uuid::new_v4();
+    let filter = TransactionFilter { category_id: Some(unrelated_category), ..Default::default() };
+    assert!(budget.filter_transactions(&filter).is_empty());
+}
+
+#[test]
+fn filter_transactions_matches_category_even_with_zero_net_amount() {
+    let account_id = Uuid::new_v4();
+    let category_id = Uuid::new_v4();
+
+    let mut budget = Budget::default();
+    let mut tx = transaction("2025-06-20", account_id, Some(category_id), "settled");
+    tx.inflow = Milliunits::from_decimal_str("10.00").unwrap();
+    tx.outflow = Milliunits::from_decimal_str("10.00").unwrap();
+    budget.transactions.push(tx);
+
+    let filter = TransactionFilter { category_id: Some(category_id), ..Default::default() };
+    assert_eq!(budget.filter_transactions(&filter).len(), 1);
+}
+
+#[test]
+fn filter_transactions_applies_account_and_status_filters() {
+    let account_a = Uuid::new_v4();
+    let account_b = Uuid::new_v4();
+
+    let mut budget = Budget::default();
+    budget.transactions.push(transaction("2025-06-01", account_a, None, "settled"));
+    budget.transactions.push(transaction("2025-06-02", account_b, None, "unreconciled"));
+
+    let filter = TransactionFilter { account_id: Some(account_a), ..Default::default() };
+    assert_eq!(budget.filter_transactions(&filter).len(), 1);
+
+    let filter = TransactionFilter { status: Some("unreconciled".into()), ..Default::default() };
+    let matching = budget.filter_transactions(&filter);
+    assert_eq!(matching.len(), 1);
+    assert_eq!(matching[0].account_id, account_b);
+}
+
+#[test]
+fn paginate_transactions_resumes_strictly_after_cursor() {
+    let account_id = Uuid::new_v4();
+    let mut budget = Budget::default();
+    for date in ["2025-06-01", "2025-06-02", "2025-06-03"] {
+        budget.transactions.push(transaction(date, account_id, None, "settled"));
+    }
+
+    let matching = budget.filter_transactions(&TransactionFilter::default());
+    let (first_page, cursor) = Budget::paginate_transactions(&matching, None, 2);
+    assert_eq!(first_page.len(), 2);
+    let cursor = cursor.expect("a third transaction remains");
+
+    let (second_page, next_cursor) =
+        Budget::paginate_transactions(&matching, Some((&cursor.0, cursor.1)), 2);
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page[0].date, "2025-06-03");
+    assert!(next_cursor.is_none());
+}
+
+#[test]
+fn create_scheduled_transaction_stamps_server_knowledge() {
+    let mut budget = Budget::default();
+    let created = budget.create_scheduled_transaction(ScheduledTransaction {
+        id: Uuid::new_v4(),
+        date_first: Date::parse("2025-06-01").unwrap(),
+        frequency: Frequency::Monthly,
+        account_id: Uuid::new_v4(),
+        category_id: None,
+        payee: Some("Landlord".into()),
+        inflow: Milliunits::ZERO,
+        outflow: Milliunits::from_decimal_str("1200.00").unwrap(),
         memo: None,
+        last_posted: None,
+        last_modified_knowledge: 0,
     });
 
-    assert_eq!(budget.category_balance(cat_id), 30.0);
-    assert_eq!(budget.account_balance(account_id), 80.0);
-    // after transfer 50 to cat_id, available decreases
-    assert_eq!(budget.available_to_budget(), -50.0);
+    assert_eq!(created.last_modified_knowledge, 1);
+    assert_eq!(budget.server_knowledge, 1);
+    assert_eq!(budget.scheduled_transactions[0].last_modified_knowledge, 1);
+}
+
+#[test]
+fn posting_a_scheduled_transaction_restamps_its_own_knowledge() {
+    let mut budget = Budget::default();
+    let sched = budget.create_scheduled_transaction(ScheduledTransaction {
+        id: Uuid::new_v4(),
+        date_first: Date::parse("2025-06-01").unwrap(),
+        frequency: Frequency::Monthly,
+        account_id: Uuid::new_v4(),
+        category_id: None,
+        payee: Some("Landlord".into()),
+        inflow: Milliunits::ZERO,
+        outflow: Milliunits::from_decimal_str("1200.00").unwrap(),
+        memo: None,
+        last_posted: None,
+        last_modified_knowledge: 0,
+    });
+    let created_knowledge = sched.last_modified_knowledge;
+
+    budget.post_due_scheduled_transaction(sched.id).expect("schedule has a due occurrence");
+
+    let updated = budget.scheduled_transactions.iter().find(|s| s.id == sched.id).unwrap();
+    assert_eq!(updated.last_posted, Some(Date::parse("2025-06-01").unwrap()));
+    assert!(updated.last_modified_knowledge > created_knowledge);
 }